@@ -9,6 +9,13 @@ use std::sync::Arc;
 use super::FontEntry;
 use cachelib::{sync::SyncCache, ValueSize};
 use std::hash::{Hash, Hasher};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use font_kit::family_name::FamilyName;
+use font_kit::handle::Handle;
+use font_kit::properties::{Properties, Style, Weight};
+use font_kit::source::SystemSource;
+use pathfinder_content::outline::Outline;
 
 pub static STANDARD_FONTS: &[(&'static str, &'static str)] = &[
     ("Courier", "CourierStd.otf"),
@@ -71,12 +78,209 @@ impl Hash for FontRc {
         Arc::as_ptr(&self.0).hash(state)
     }
 }
+impl ValueSize for Outline {
+    #[inline]
+    fn size(&self) -> usize {
+        // Proportional to point count rather than a flat 1, so a cache full of
+        // complex glyph outlines (CJK, decorative faces) evicts before one full
+        // of simple ones (Latin sans-serif) at the same entry count.
+        self.contours().iter().map(|c| c.len()).sum::<usize>() + 1
+    }
+}
+
 pub struct StandardCache {
-    inner: Arc<SyncCache<usize, Option<FontRc>>>
+    inner: Arc<SyncCache<usize, Option<FontRc>>>,
+    system: Mutex<HashMap<String, Option<FontRc>>>,
+    /// Tessellated glyph outlines, keyed by the font they came from and their
+    /// glyph index, so a document that repeats the same letters thousands of
+    /// times only walks each glyph's outline builder once.
+    ///
+    /// NOTE: the scene-building loop that draws text runs glyph-by-glyph
+    /// lives in this crate's root (`render/src/lib.rs`), which is not part of
+    /// this checkout, so nothing calls `glyph_outline` yet. Whoever builds a
+    /// `Scene` from a page's content stream must route each glyph lookup
+    /// through `StandardCache::glyph_outline(&self.cache, &font, gid, || ...)`
+    /// instead of invoking the font's own outline builder directly, or this
+    /// cache buys nothing.
+    glyphs: Arc<SyncCache<(FontRc, u32), Arc<Outline>>>,
 }
 impl StandardCache {
     pub fn new() -> Self {
-        StandardCache { inner: SyncCache::new() }
+        StandardCache {
+            inner: SyncCache::new(),
+            system: Mutex::new(HashMap::new()),
+            glyphs: SyncCache::new(),
+        }
+    }
+
+    /// Returns the cached outline (in font units) for `gid` of `font`, building
+    /// it with `build` on first use. `build` runs again for a pair only after
+    /// its entry has been evicted, not for each occurrence of the glyph.
+    pub fn glyph_outline(&self, font: &FontRc, gid: u32, build: impl FnOnce() -> Outline) -> Arc<Outline> {
+        self.glyphs.get((font.clone(), gid), || Arc::new(build()))
+    }
+}
+
+/// Map the PDF `FontDescriptor` flags and the font name onto `font-kit` properties,
+/// so `SystemSource::select_best_match` can find an installed substitute.
+fn system_font_query(pdf_font: &PdfFont, resolve: &impl Resolve) -> (Vec<FamilyName>, Properties) {
+    let name = pdf_font.name.as_ref().map(|s| s.as_str()).unwrap_or("");
+
+    let descriptor = pdf_font.descriptor(resolve);
+    let flags = descriptor.as_ref().map(|d| d.flags).unwrap_or(0);
+    let fixed_pitch = flags & 1 != 0;
+    let serif = flags & (1 << 1) != 0;
+    let force_bold = flags & (1 << 18) != 0;
+
+    let italic = flags & (1 << 6) != 0
+        || name.ends_with("-Italic")
+        || name.ends_with("-Oblique")
+        || name.ends_with(",Italic")
+        || name.contains("Italic")
+        || name.contains("Oblique");
+    let bold = force_bold || name.ends_with("-Bold") || name.contains("Bold");
+
+    let style = if italic { Style::Italic } else { Style::Normal };
+    let weight = if bold { Weight::BOLD } else { Weight::NORMAL };
+
+    let generic = if fixed_pitch {
+        FamilyName::Monospace
+    } else if serif {
+        FamilyName::Serif
+    } else {
+        FamilyName::SansSerif
+    };
+
+    let mut families = Vec::with_capacity(2);
+    let family = name.split(&[',', '-', '+'][..]).next().unwrap_or(name);
+    if !family.is_empty() {
+        families.push(FamilyName::Title(family.to_owned()));
+    }
+    families.push(generic);
+
+    (families, Properties { style, weight, ..Properties::new() })
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FontClass {
+    Fixed,
+    Serif,
+    Sans,
+}
+
+/// Classify one of the `STANDARD_FONTS` entries by its PostScript name.
+fn classify_standard(name: &str) -> (FontClass, bool, bool) {
+    let class = if name.starts_with("Courier") {
+        FontClass::Fixed
+    } else if name.starts_with("Times") || name.starts_with("TimesNewRoman") {
+        FontClass::Serif
+    } else {
+        FontClass::Sans
+    };
+    let bold = name.contains("Bold");
+    let italic = name.contains("Italic") || name.contains("Oblique");
+    (class, bold, italic)
+}
+
+/// Scores every `STANDARD_FONTS` entry against a descriptor's class/weight/slant
+/// and returns the index of the best substitute, so vendor-mangled or
+/// subset-prefixed names (`Helvetica-Condensed`, `TimesNewRoman,Bold`, ...)
+/// still get a sane substitution.
+fn score_standard_font(name: &str, class: FontClass, bold: bool, italic: bool) -> Option<usize> {
+    // Symbol and ZapfDingbats use their own glyph encoding rather than a
+    // Latin-script one, so they can only ever be a match for themselves.
+    if let Some(i) = STANDARD_FONTS.iter().position(|&(std_name, _)| std_name == name) {
+        if name == "Symbol" || name == "ZapfDingbats" {
+            return Some(i);
+        }
+    }
+
+    // A class match alone (4) is too weak to trust on its own -- every
+    // STANDARD_FONTS class happens to carry all four bold/italic
+    // combinations, so the winning entry always matches class *and*
+    // weight/slant anyway (score 7) whenever the descriptor gave real
+    // signal. Requiring a second criterion here just guards against that
+    // invariant ever drifting as STANDARD_FONTS changes; the thing that
+    // actually keeps unrelated fonts out of this path is `best_standard_font`
+    // refusing to score at all without a `FontDescriptor`.
+    const MIN_SCORE: u32 = 5;
+
+    STANDARD_FONTS.iter().enumerate()
+        .filter(|(_, &(std_name, _))| std_name != "Symbol" && std_name != "ZapfDingbats")
+        .map(|(i, &(std_name, _))| {
+            let (std_class, std_bold, std_italic) = classify_standard(std_name);
+            let mut score = 0;
+            if std_class == class { score += 4; }
+            if std_bold == bold { score += 2; }
+            if std_italic == italic { score += 1; }
+            (i, score)
+        })
+        .max_by_key(|&(_, score)| score)
+        .filter(|&(_, score)| score >= MIN_SCORE)
+        .map(|(i, _)| i)
+}
+
+/// Maps the PDF `FontDescriptor` flags and the font name onto a `STANDARD_FONTS`
+/// substitute via `score_standard_font`.
+fn best_standard_font(pdf_font: &PdfFont, resolve: &impl Resolve) -> Option<usize> {
+    let name = pdf_font.name.as_ref().map(|s| s.as_str()).unwrap_or("");
+
+    let descriptor = pdf_font.descriptor(resolve);
+    if descriptor.is_none() && name != "Symbol" && name != "ZapfDingbats" {
+        // No FontDescriptor means no real classification signal: defaulting
+        // flags to 0 would resolve every such font to Sans/regular and
+        // "match" Helvetica on class alone. Defer to the system-font lookup
+        // instead of guessing.
+        return None;
+    }
+    let flags = descriptor.as_ref().map(|d| d.flags).unwrap_or(0);
+
+    let fixed_pitch = flags & 1 != 0;
+    let serif = flags & (1 << 1) != 0;
+    let force_bold = flags & (1 << 18) != 0;
+    let descriptor_italic = flags & (1 << 6) != 0
+        || descriptor.as_ref().map(|d| d.italic_angle != 0.0).unwrap_or(false);
+
+    let class = if fixed_pitch {
+        FontClass::Fixed
+    } else if serif {
+        FontClass::Serif
+    } else {
+        FontClass::Sans
+    };
+    let bold = force_bold
+        || descriptor.as_ref().map(|d| d.font_weight.unwrap_or(400.) >= 600.).unwrap_or(false)
+        || name.contains("Bold");
+    let italic = descriptor_italic || name.contains("Italic") || name.contains("Oblique");
+
+    score_standard_font(name, class, bold, italic)
+}
+
+fn load_system_font(pdf_font: &PdfFont, resolve: &impl Resolve) -> Option<FontRc> {
+    let (families, properties) = system_font_query(pdf_font, resolve);
+    let handle = SystemSource::new().select_best_match(&families, &properties).ok()?;
+    let (data, font_index) = match handle {
+        Handle::Path { path, font_index } => (std::fs::read(&path).ok()?, font_index),
+        Handle::Memory { bytes, font_index } => ((*bytes).clone(), font_index),
+    };
+    if font_index != 0 {
+        // `font::parse` takes raw font bytes with no way to select a face
+        // inside a TrueType/OpenType collection (`.ttc`/`.otc`), so parsing
+        // `data` as-is would either fail or silently pick face 0 instead of
+        // the face font-kit actually matched. Bail rather than mis-render.
+        warn!(
+            "system font for {:?} resolved to face {} of a font collection, which isn't \
+             supported here; skipping instead of rendering the wrong face",
+            pdf_font.name, font_index
+        );
+        return None;
+    }
+    match font::parse(&data) {
+        Ok(f) => Some(f.into()),
+        Err(e) => {
+            warn!("system font for {:?} failed to parse: {:?}", pdf_font.name, e);
+            None
+        }
     }
 }
 
@@ -96,8 +300,8 @@ pub fn load_font(font_ref: Ref<PdfFont>, resolve: &impl Resolve, standard_fonts:
         }
         Some(Err(e)) => return Err(e),
         None => {
-            match STANDARD_FONTS.iter().enumerate().find(|(_, &(name, _))| pdf_font.name.as_ref().map(|s| s == name).unwrap_or(false)) {
-                Some((i, &(_, file_name))) => {
+            match best_standard_font(&pdf_font, resolve).map(|i| (i, STANDARD_FONTS[i])) {
+                Some((i, (_, file_name))) => {
                     let val = cache.inner.get(i, || {
                         let data = match std::fs::read(standard_fonts.join(file_name)) {
                             Ok(data) => data,
@@ -122,8 +326,33 @@ pub fn load_font(font_ref: Ref<PdfFont>, resolve: &impl Resolve, standard_fonts:
                     }
                 }
                 None => {
-                    warn!("no font for {:?}", pdf_font.name);
-                    return Ok(None);
+                    let key = pdf_font.name.as_ref().map(|s| s.as_str()).unwrap_or("").to_owned();
+                    if let Some(val) = cache.system.lock().unwrap().get(&key).cloned() {
+                        match val {
+                            Some(f) => f,
+                            None => {
+                                warn!("no font for {:?}", pdf_font.name);
+                                return Ok(None);
+                            }
+                        }
+                    } else {
+                        // The disk read + font-kit match below can be slow; do it
+                        // without holding the lock so concurrent page renders that
+                        // need *different* uncached system fonts don't serialize
+                        // behind each other. A race just means two threads compute
+                        // the same font once instead of one -- cheaper than forcing
+                        // every thread through a single global lock.
+                        let font = load_system_font(&pdf_font, resolve);
+                        let val = cache.system.lock().unwrap()
+                            .entry(key).or_insert_with(|| font).clone();
+                        match val {
+                            Some(f) => f,
+                            None => {
+                                warn!("no font for {:?}", pdf_font.name);
+                                return Ok(None);
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -131,3 +360,54 @@ pub fn load_font(font_ref: Ref<PdfFont>, resolve: &impl Resolve, standard_fonts:
 
     Ok(Some(FontEntry::build(font, pdf_font, resolve)?))
 }
+
+#[cfg(test)]
+mod standard_font_tests {
+    use super::*;
+
+    #[test]
+    fn classify_standard_reads_family_weight_and_slant_from_the_name() {
+        assert_eq!(classify_standard("Courier-BoldOblique"), (FontClass::Fixed, true, true));
+        assert_eq!(classify_standard("Times-Italic"), (FontClass::Serif, false, true));
+        assert_eq!(classify_standard("TimesNewRomanPS-BoldMT"), (FontClass::Serif, true, false));
+        assert_eq!(classify_standard("Helvetica"), (FontClass::Sans, false, false));
+        assert_eq!(classify_standard("ArialMT"), (FontClass::Sans, false, false));
+    }
+
+    #[test]
+    fn score_standard_font_symbol_and_zapfdingbats_only_match_themselves() {
+        assert_eq!(
+            STANDARD_FONTS[score_standard_font("Symbol", FontClass::Sans, false, false).unwrap()].0,
+            "Symbol"
+        );
+        assert_eq!(
+            STANDARD_FONTS[score_standard_font("ZapfDingbats", FontClass::Fixed, true, true).unwrap()].0,
+            "ZapfDingbats"
+        );
+        // Nothing else is allowed to win against Symbol/ZapfDingbats's own
+        // glyph encoding, no matter how well the class/weight/slant matches.
+        assert_ne!(
+            STANDARD_FONTS[score_standard_font("Helvetica-Condensed", FontClass::Sans, false, false).unwrap()].0,
+            "Symbol"
+        );
+    }
+
+    #[test]
+    fn score_standard_font_picks_the_closest_class_weight_and_slant() {
+        let best = score_standard_font("Helvetica-Condensed", FontClass::Sans, true, false).unwrap();
+        let (class, bold, italic) = classify_standard(STANDARD_FONTS[best].0);
+        assert_eq!(class, FontClass::Sans);
+        assert!(bold);
+        assert!(!italic);
+    }
+
+    #[test]
+    fn score_standard_font_class_match_outweighs_weight_and_slant() {
+        // A class match is worth more (4) than a perfect weight+slant match
+        // on the wrong class (2 + 1 = 3), so the winner is always in the
+        // requested class even if nothing else about it matches.
+        let best = score_standard_font("Unknown", FontClass::Serif, true, true).unwrap();
+        let (class, _, _) = classify_standard(STANDARD_FONTS[best].0);
+        assert_eq!(class, FontClass::Serif);
+    }
+}
@@ -0,0 +1,264 @@
+//! C ABI for embedding the renderer from other languages (C, Python via
+//! `ctypes`, Node via `node-ffi`) without reimplementing the event loop.
+//!
+//! Enabled by the `capi` feature; build this crate as a `cdylib`/`staticlib`
+//! to get a loadable shared/static library out of it.
+
+use std::os::raw::c_void;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::slice;
+
+use pathfinder_color::ColorF;
+use pathfinder_geometry::rect::RectF;
+use pathfinder_geometry::transform2d::Transform2F;
+use pathfinder_geometry::vector::{vec2f, vec2i, Vector2F};
+use pathfinder_gl::GLDevice;
+use pathfinder_renderer::scene::Scene;
+use pathfinder_resources::embedded::EmbeddedResourceLoader;
+use surfman::{Connection, ContextAttributeFlags, ContextAttributes, GLApi, GLVersion, SurfaceAccess, SurfaceType};
+
+use pdf::file::File as PdfFile;
+
+use crate::headless;
+use crate::viewer::{round_v_to_16, Config, Context, DEFAULT_SCALE};
+
+/// Renders every page once at unit scale to learn its size in document
+/// space, independent of `Context::scale`, so `Context::set_page_layout`
+/// can lay out `ScrollMode::Continuous` before anything is actually drawn.
+/// A page that fails to parse contributes a zero-size rect rather than
+/// aborting the whole document load.
+fn page_sizes(file: &PdfFile<Vec<u8>>) -> Vec<Vector2F> {
+    let resolver = file.resolver();
+    (0..file.num_pages())
+        .map(|i| {
+            file.get_page(i)
+                .ok()
+                .and_then(|page| render::render_page(file, &resolver, &page, 1.0).ok())
+                .map(|scene| {
+                    let view_box = scene.view_box();
+                    if view_box == RectF::default() { scene.bounds().size() } else { view_box.size() }
+                })
+                .unwrap_or(Vector2F::default())
+        })
+        .collect()
+}
+
+/// Builds an offscreen GL device of `size`, for `pdf_render_page` to render
+/// into without an on-screen window.
+fn offscreen_device(size: pathfinder_geometry::vector::Vector2I) -> Option<GLDevice> {
+    // `headless::render_to_buffer` rounds the framebuffer up to a multiple of
+    // 16, same as any other `Context` window; size the surface to match or
+    // the texture attached to it won't fit.
+    let size = round_v_to_16(size);
+    let connection = Connection::new().ok()?;
+    let adapter = connection.create_software_adapter().ok()?;
+    let gl_device = connection.create_device(&adapter).ok()?;
+    let attributes = ContextAttributes {
+        version: GLVersion::new(3, 0),
+        flags: ContextAttributeFlags::empty(),
+    };
+    let descriptor = gl_device.create_context_descriptor(&attributes).ok()?;
+    let mut gl_device = gl_device;
+    let mut context = gl_device.create_context(&descriptor, None).ok()?;
+    let surface = gl_device
+        .create_surface(&context, SurfaceAccess::GPUOnly, SurfaceType::Generic { size })
+        .ok()?;
+    gl_device.bind_surface_to_context(&mut context, surface).ok()?;
+    gl_device.make_context_current(&context).ok()?;
+    Some(GLDevice::new(GLApi::GL, gl_device.context_surface_info(&context).ok()??.framebuffer_object))
+}
+
+/// Status codes returned across the FFI boundary instead of panicking.
+#[repr(C)]
+pub enum PdfStatus {
+    Ok = 0,
+    InvalidHandle = 1,
+    LoadError = 2,
+    RenderError = 3,
+    BufferTooSmall = 4,
+    Panic = 5,
+}
+
+/// Opaque handle wrapping a loaded document and its view `Context`.
+pub struct PdfHandle {
+    file: PdfFile<Vec<u8>>,
+    ctx: Context,
+}
+
+fn guard<F: FnOnce() -> PdfStatus>(f: F) -> PdfStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(status) => status,
+        Err(_) => PdfStatus::Panic,
+    }
+}
+
+/// Loads a PDF from `data[0..len)` and writes a handle to `out_handle`, or
+/// null on failure. The handle must be released with `pdf_render_destroy`.
+///
+/// `data` is untrusted external input, so the actual parse runs inside
+/// `guard()` like every other entry point here: a malformed/malicious PDF
+/// that makes the parser panic returns `PdfStatus::Panic` with `*out_handle`
+/// left null instead of unwinding across the `extern "C"` boundary.
+#[no_mangle]
+pub extern "C" fn pdf_render_create(data: *const u8, len: usize, out_handle: *mut *mut PdfHandle) -> PdfStatus {
+    guard(|| {
+        if out_handle.is_null() {
+            return PdfStatus::InvalidHandle;
+        }
+        unsafe { *out_handle = std::ptr::null_mut() };
+        if data.is_null() {
+            return PdfStatus::InvalidHandle;
+        }
+        let bytes = unsafe { slice::from_raw_parts(data, len) }.to_vec();
+        let file = match PdfFile::from_data(bytes) {
+            Ok(file) => file,
+            Err(_) => return PdfStatus::LoadError,
+        };
+        let sizes = page_sizes(&file);
+
+        let config = Config::new(Box::new(EmbeddedResourceLoader));
+        let mut ctx = Context::new(config, Default::default());
+        ctx.set_page_layout(&sizes);
+
+        unsafe { *out_handle = Box::into_raw(Box::new(PdfHandle { file, ctx })) };
+        PdfStatus::Ok
+    })
+}
+
+/// Frees a handle created with `pdf_render_create`. Safe to call with null.
+#[no_mangle]
+pub extern "C" fn pdf_render_destroy(handle: *mut PdfHandle) {
+    if !handle.is_null() {
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+/// Writes the document's page count to `out_num_pages`.
+#[no_mangle]
+pub extern "C" fn pdf_render_num_pages(handle: *const PdfHandle, out_num_pages: *mut usize) -> PdfStatus {
+    guard(|| {
+        if out_num_pages.is_null() {
+            return PdfStatus::InvalidHandle;
+        }
+        let handle = match unsafe { handle.as_ref() } {
+            Some(handle) => handle,
+            None => return PdfStatus::InvalidHandle,
+        };
+        unsafe { *out_num_pages = handle.ctx.num_pages };
+        PdfStatus::Ok
+    })
+}
+
+/// Sets the zoom factor (device-independent, `view::DEFAULT_SCALE` is 1:1).
+#[no_mangle]
+pub extern "C" fn pdf_render_set_scale(handle: *mut PdfHandle, scale: f32) -> PdfStatus {
+    guard(|| {
+        let handle = match unsafe { handle.as_mut() } {
+            Some(handle) => handle,
+            None => return PdfStatus::InvalidHandle,
+        };
+        handle.ctx.set_zoom(if scale > 0.0 { scale } else { DEFAULT_SCALE });
+        PdfStatus::Ok
+    })
+}
+
+/// Centers the view on `(x, y)` in document space.
+#[no_mangle]
+pub extern "C" fn pdf_render_set_view_center(handle: *mut PdfHandle, x: f32, y: f32) -> PdfStatus {
+    guard(|| {
+        let handle = match unsafe { handle.as_mut() } {
+            Some(handle) => handle,
+            None => return PdfStatus::InvalidHandle,
+        };
+        handle.ctx.move_to(vec2f(x, y));
+        PdfStatus::Ok
+    })
+}
+
+/// Advances to page `page` (clamped to the document's page range).
+#[no_mangle]
+pub extern "C" fn pdf_render_goto_page(handle: *mut PdfHandle, page: usize) -> PdfStatus {
+    guard(|| {
+        let handle = match unsafe { handle.as_mut() } {
+            Some(handle) => handle,
+            None => return PdfStatus::InvalidHandle,
+        };
+        handle.ctx.goto_page(page);
+        PdfStatus::Ok
+    })
+}
+
+/// Renders the pages currently on screen into `out_pixels`, an RGBA8 buffer
+/// of `width * height * 4` bytes provided by the caller. In `ScrollMode::SinglePage` that's just `pdf_render_goto_page`'s
+/// target; in `ScrollMode::Continuous` it's every page `Context::visible_pages`
+/// reports as intersecting the viewport, composited into one scene at the
+/// document-space offsets `pdf_render_create` laid them out at.
+///
+/// The framebuffer is rounded up to a multiple of 16 like any other `Context`
+/// window. When that rounded size needs more than `width * height * 4` bytes,
+/// nothing is written and `PdfStatus::BufferTooSmall` is returned, so for
+/// non-zero `width` and `height` the call succeeds only when both are
+/// multiples of 16. On success `out_width`/`out_height` report the
+/// dimensions the pixels were written at (row stride `out_width * 4`).
+#[no_mangle]
+pub extern "C" fn pdf_render_page(
+    handle: *mut PdfHandle,
+    out_pixels: *mut u8,
+    width: u32,
+    height: u32,
+    out_width: *mut u32,
+    out_height: *mut u32,
+) -> PdfStatus {
+    guard(|| {
+        if out_pixels.is_null() || out_width.is_null() || out_height.is_null() {
+            return PdfStatus::InvalidHandle;
+        }
+        let handle = match unsafe { handle.as_mut() } {
+            Some(handle) => handle,
+            None => return PdfStatus::InvalidHandle,
+        };
+        let pages_to_draw = handle.ctx.visible_pages();
+        if pages_to_draw.is_empty() {
+            return PdfStatus::RenderError;
+        }
+        let mut scene = Scene::new();
+        for (page_nr, rect) in pages_to_draw {
+            let page = match handle.file.get_page(page_nr as u32) {
+                Ok(page) => page,
+                Err(_) => return PdfStatus::LoadError,
+            };
+            let page_scene = match render::render_page(&handle.file, &handle.file.resolver(), &page, 1.0) {
+                Ok(scene) => scene,
+                Err(_) => return PdfStatus::RenderError,
+            };
+            scene.append_scene(page_scene, Transform2F::from_translation(rect.origin()));
+        }
+        let requested_size = vec2i(width as i32, height as i32);
+        let device = match offscreen_device(requested_size) {
+            Some(device) => device,
+            None => return PdfStatus::RenderError,
+        };
+        let (pixels, actual_size) = headless::render_to_buffer(
+            &mut scene,
+            &mut handle.ctx,
+            device,
+            Box::new(EmbeddedResourceLoader),
+            requested_size,
+            handle.ctx.scale,
+            ColorF::white(),
+        );
+        let needed = (actual_size.x() as usize) * (actual_size.y() as usize) * 4;
+        if (width as usize) * (height as usize) * 4 < needed {
+            return PdfStatus::BufferTooSmall;
+        }
+        unsafe {
+            std::ptr::copy_nonoverlapping(pixels.as_ptr(), out_pixels, needed);
+            *out_width = actual_size.x() as u32;
+            *out_height = actual_size.y() as u32;
+        }
+        PdfStatus::Ok
+    })
+}
+
+#[allow(dead_code)]
+fn _assert_send_sync_ptr(_: *mut c_void) {}
@@ -0,0 +1,71 @@
+//! Offscreen rendering, for turning a page into pixels without a window
+//! (thumbnails, server-side PDF-to-image, golden-image tests).
+
+use pathfinder_color::ColorF;
+use pathfinder_geometry::rect::RectI;
+use pathfinder_geometry::vector::{vec2i, Vector2I};
+use pathfinder_gpu::{Device, RenderTarget, TextureDataReceiver};
+use pathfinder_renderer::concurrent::executor::SequentialExecutor;
+use pathfinder_renderer::gpu::{
+    options::{DestFramebuffer, RendererMode, RendererOptions},
+    renderer::Renderer,
+};
+use pathfinder_renderer::options::BuildOptions;
+use pathfinder_resources::ResourceLoader;
+
+use crate::viewer::{round_v_to_16, Context, Interactive};
+
+/// Renders `interactive`'s current page into an offscreen RGBA8 buffer.
+///
+/// `device` must already be bound to an offscreen context (e.g. a pbuffer
+/// created with `surfman`), sized to fit at least `round_v_to_16(requested_size)`
+/// pixels; `scale` plays the same role as `Context::scale` for the interactive
+/// viewers. The framebuffer is rounded up to a multiple of 16 like any other
+/// `Context` window, so the returned dimensions may exceed `requested_size` —
+/// callers MUST use the returned `Vector2I`, not `requested_size`, as the
+/// buffer's actual width/height/stride. Returns the raw pixels, top-to-bottom,
+/// together with those dimensions.
+pub fn render_to_buffer<T, D>(
+    interactive: &mut T,
+    ctx: &mut Context,
+    mut device: D,
+    resource_loader: Box<dyn ResourceLoader>,
+    requested_size: Vector2I,
+    scale: f32,
+    background: ColorF,
+) -> (Vec<u8>, Vector2I)
+where
+    T: Interactive,
+    D: Device,
+{
+    ctx.set_zoom(scale);
+    ctx.set_window_size(requested_size.to_f32());
+    let scene = interactive.scene(ctx);
+
+    let framebuffer_size = round_v_to_16(requested_size);
+
+    let dest_texture = device.create_texture(pathfinder_gpu::TextureFormat::RGBA8, framebuffer_size);
+    let dest_framebuffer = device.create_framebuffer(dest_texture);
+
+    let mode = RendererMode { level: ctx.render_level() };
+    let options = RendererOptions {
+        dest: DestFramebuffer::Other(dest_framebuffer),
+        background_color: Some(background),
+        ..RendererOptions::default()
+    };
+    let mut renderer = Renderer::new(device, &*resource_loader, mode, options);
+
+    let build_options = BuildOptions {
+        transform: pathfinder_renderer::options::RenderTransform::Transform2D(ctx.view_transform()),
+        ..BuildOptions::default()
+    };
+    scene.build_and_render(&mut renderer, build_options, SequentialExecutor);
+
+    let rect = RectI::new(vec2i(0, 0), framebuffer_size);
+    let pixels = renderer
+        .device()
+        .read_pixels(&RenderTarget::Framebuffer(renderer.framebuffer()), rect)
+        .to_vec();
+
+    (pixels, framebuffer_size)
+}
@@ -0,0 +1,5 @@
+pub mod viewer;
+pub mod headless;
+
+#[cfg(feature = "capi")]
+pub mod capi;
@@ -94,6 +94,16 @@ impl Interactive for Scene {
     }
 }
 
+/// How `Context` lays out and scrolls through the document's pages.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScrollMode {
+    /// One page is active at a time; PageUp/PageDown jump between pages.
+    SinglePage,
+    /// Pages are stacked vertically with `Config::page_gap` between them and
+    /// scrolling moves smoothly across page boundaries.
+    Continuous,
+}
+
 pub struct Config {
     pub zoom: bool,
     pub pan: bool,
@@ -103,6 +113,8 @@ pub struct Config {
     pub render_level: RendererLevel,
     pub resource_loader: Box<dyn ResourceLoader>,
     pub threads: bool,
+    pub scroll_mode: ScrollMode,
+    pub page_gap: f32,
 }
 impl Config {
     pub fn new(resource_loader: Box<dyn ResourceLoader>) -> Self {
@@ -115,6 +127,8 @@ impl Config {
             render_level: RendererLevel::D3D9,
             resource_loader,
             threads: true,
+            scroll_mode: ScrollMode::SinglePage,
+            page_gap: 8.0,
         }
     }
 }
@@ -131,6 +145,10 @@ pub struct Context {
     pub(crate) scale_factor: f32,     // device dependend
     pub(crate) config: Config,
     pub(crate) bounds: Option<RectF>,
+    /// Per-page rects in document space. In `ScrollMode::SinglePage` these all
+    /// share the same origin; in `ScrollMode::Continuous` they are stacked
+    /// vertically, separated by `Config::page_gap`.
+    pub(crate) page_rects: Vec<RectF>,
     pub(crate) close: bool,
     pub update_interval: Option<f32>,
     pub pixel_scroll_factor: Vector2F,
@@ -153,6 +171,7 @@ impl Context {
             view_center: Vector2F::default(),
             window_size: Vector2F::default(),
             bounds: None,
+            page_rects: Vec::new(),
             close: false,
             update_interval: None,
             pixel_scroll_factor,
@@ -165,24 +184,79 @@ impl Context {
         self.redraw_requested = true;
     }
     
+    /// Lays out `page_sizes` according to `Config::scroll_mode` and updates
+    /// `num_pages` and the document `bounds` accordingly.
+    pub fn set_page_layout(&mut self, page_sizes: &[Vector2F]) {
+        self.num_pages = page_sizes.len();
+        self.page_rects = match self.config.scroll_mode {
+            ScrollMode::SinglePage => page_sizes.iter()
+                .map(|&size| RectF::new(Vector2F::default(), size))
+                .collect(),
+            ScrollMode::Continuous => {
+                let mut y = 0.0;
+                page_sizes.iter().map(|&size| {
+                    let rect = RectF::new(vec2f(0.0, y), size);
+                    y += size.y() + self.config.page_gap;
+                    rect
+                }).collect()
+            }
+        };
+        if let Some(doc_bounds) = self.page_rects.iter().fold(None, |acc: Option<RectF>, &r| {
+            Some(acc.map_or(r, |b| b.union_rect(r)))
+        }) {
+            self.set_bounds(doc_bounds);
+        }
+        self.page_nr = self.page_nr.min(self.num_pages.saturating_sub(1));
+        self.request_redraw();
+    }
+
+    /// The page whose rect the current `view_center` falls into (continuous
+    /// mode); in `ScrollMode::SinglePage` this is just `page_nr`.
+    fn current_page(&self) -> usize {
+        page_at(&self.page_rects, self.config.scroll_mode, self.page_nr, self.view_center)
+    }
+
+    /// The currently visible rect, in document space.
+    pub fn viewport_rect(&self) -> RectF {
+        let half_size = self.window_size * (0.5 / self.scale);
+        RectF::new(self.view_center - half_size, half_size * 2.0)
+    }
+
+    /// The `(page index, page rect)` pairs, in document space, whose rect
+    /// intersects the current viewport, for the `scene` call to composite.
+    /// In `ScrollMode::SinglePage` this is just the current page.
+    pub fn visible_pages(&self) -> Vec<(usize, RectF)> {
+        pages_in_viewport(&self.page_rects, self.config.scroll_mode, self.page_nr, self.viewport_rect())
+    }
+
     pub fn goto_page(&mut self, page: usize) {
-        let page = page.min(self.num_pages - 1);
-        if page != self.page_nr {
-            self.page_nr = page;
-            self.request_redraw();
+        let page = page.min(self.num_pages.saturating_sub(1));
+        match self.config.scroll_mode {
+            ScrollMode::SinglePage => {
+                if page != self.page_nr {
+                    self.page_nr = page;
+                    self.request_redraw();
+                }
+            }
+            ScrollMode::Continuous => {
+                if let Some(rect) = self.page_rects.get(page) {
+                    let y = rect.origin_y() + rect.height() * 0.5;
+                    self.move_to(vec2f(self.view_center.x(), y));
+                }
+            }
         }
     }
-    
+
     pub fn next_page(&mut self) {
-        self.goto_page(self.page_nr.saturating_add(1));
+        self.goto_page(self.current_page().saturating_add(1));
     }
-    
+
     pub fn prev_page(&mut self) {
-        self.goto_page(self.page_nr.saturating_sub(1));
+        self.goto_page(self.current_page().saturating_sub(1));
     }
-    
+
     pub fn page_nr(&self) -> usize {
-        self.page_nr
+        self.current_page()
     }
     
     pub fn zoom_by(&mut self, log2_factor: f32) {
@@ -238,6 +312,7 @@ impl Context {
     pub fn move_to(&mut self, point: Vector2F) {
         self.view_center = point;
         self.check_bounds();
+        self.page_nr = self.current_page();
         self.request_redraw();
     }
 
@@ -289,6 +364,10 @@ impl Context {
         self.window_size *= s;
     }
 
+    pub fn render_level(&self) -> RendererLevel {
+        self.config.render_level
+    }
+
     pub fn send(&mut self, data: Vec<u8>) {}
 
     pub fn set_icon(&mut self, icon: Icon) {
@@ -304,3 +383,77 @@ fn view_box(scene: &Scene) -> RectF {
         view_box
     }
 }
+
+/// The page whose rect `view_center` falls into; `page_nr` itself outside
+/// `ScrollMode::Continuous`.
+fn page_at(page_rects: &[RectF], scroll_mode: ScrollMode, page_nr: usize, view_center: Vector2F) -> usize {
+    match scroll_mode {
+        ScrollMode::SinglePage => page_nr,
+        ScrollMode::Continuous => page_rects.iter()
+            .position(|r| view_center.y() < r.origin_y() + r.height())
+            .unwrap_or_else(|| page_rects.len().saturating_sub(1)),
+    }
+}
+
+/// The pages intersecting `viewport`, in document space.
+fn pages_in_viewport(page_rects: &[RectF], scroll_mode: ScrollMode, page_nr: usize, viewport: RectF) -> Vec<(usize, RectF)> {
+    match scroll_mode {
+        ScrollMode::SinglePage => page_rects.get(page_nr)
+            .map(|&r| vec![(page_nr, r)])
+            .unwrap_or_default(),
+        ScrollMode::Continuous => page_rects.iter().enumerate()
+            .filter(|&(_, &r)| r.intersects(viewport))
+            .map(|(i, &r)| (i, r))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod scroll_tests {
+    use super::*;
+
+    fn stacked_rects(sizes: &[(f32, f32)], gap: f32) -> Vec<RectF> {
+        let mut y = 0.0;
+        sizes.iter().map(|&(w, h)| {
+            let rect = RectF::new(vec2f(0.0, y), vec2f(w, h));
+            y += h + gap;
+            rect
+        }).collect()
+    }
+
+    #[test]
+    fn single_page_current_page_ignores_view_center() {
+        let rects = stacked_rects(&[(100.0, 200.0), (100.0, 200.0)], 8.0);
+        assert_eq!(page_at(&rects, ScrollMode::SinglePage, 1, vec2f(0.0, 9999.0)), 1);
+    }
+
+    #[test]
+    fn continuous_current_page_follows_view_center() {
+        let rects = stacked_rects(&[(100.0, 200.0), (100.0, 200.0), (100.0, 200.0)], 8.0);
+        assert_eq!(page_at(&rects, ScrollMode::Continuous, 0, vec2f(50.0, 0.0)), 0);
+        assert_eq!(page_at(&rects, ScrollMode::Continuous, 0, vec2f(50.0, 250.0)), 1);
+        assert_eq!(page_at(&rects, ScrollMode::Continuous, 0, vec2f(50.0, 9999.0)), 2);
+    }
+
+    #[test]
+    fn continuous_current_page_empty_layout_is_page_zero() {
+        assert_eq!(page_at(&[], ScrollMode::Continuous, 0, vec2f(0.0, 0.0)), 0);
+    }
+
+    #[test]
+    fn single_page_visible_pages_is_just_the_current_page() {
+        let rects = stacked_rects(&[(100.0, 200.0), (100.0, 200.0)], 8.0);
+        let viewport = RectF::new(vec2f(-1000.0, -1000.0), vec2f(2000.0, 2000.0));
+        assert_eq!(pages_in_viewport(&rects, ScrollMode::SinglePage, 1, viewport), vec![(1, rects[1])]);
+    }
+
+    #[test]
+    fn continuous_visible_pages_only_intersecting_rects() {
+        let rects = stacked_rects(&[(100.0, 200.0), (100.0, 200.0), (100.0, 200.0)], 8.0);
+        // A narrow viewport straddling the boundary between page 0 and page 1.
+        let viewport = RectF::new(vec2f(0.0, 190.0), vec2f(100.0, 30.0));
+        let visible: Vec<usize> = pages_in_viewport(&rects, ScrollMode::Continuous, 0, viewport)
+            .into_iter().map(|(i, _)| i).collect();
+        assert_eq!(visible, vec![0, 1]);
+    }
+}